@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
@@ -7,33 +7,96 @@ use axum::{
 };
 use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::any::{AnyPool, AnyPoolOptions};
 use std::env;
 use std::net::SocketAddr;
 use tokio;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::trace::TraceLayer;
+use tracing_subscriber::EnvFilter;
+use validator::Validate;
 
 // The data model for a Todo item.
 // `serde` is used for serializing and deserializing JSON.
 // `sqlx::FromRow` allows us to map database rows to this struct.
 #[derive(Serialize, Deserialize, sqlx::FromRow, Clone)]
 struct Todo {
-    id: i32,
+    // `i64` so this decodes correctly on both backends: Postgres' `SERIAL`
+    // is an `i32`-sized column, but the `Any` driver always decodes
+    // SQLite's `INTEGER` rowid as `i64`, and the cast is checked at decode
+    // time rather than statement-compile time.
+    id: i64,
     title: String,
     completed: bool,
 }
 
 // The data model for creating a new Todo item.
 // We don't need an `id` when creating a new item, as the database will generate it.
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
 struct CreateTodo {
+    #[validate(length(min = 1, max = 512))]
     title: String,
 }
 
+// The data model for a partial update to a Todo item. Every field is
+// optional so callers can update just the title, just the completed flag,
+// or both, without clobbering the fields they didn't send.
+#[derive(Deserialize, Validate)]
+struct UpdateTodo {
+    #[validate(length(min = 1, max = 512))]
+    title: Option<String>,
+    completed: Option<bool>,
+}
+
+// Query parameters accepted by `GET /todos` for pagination and filtering.
+// `page` is 1-indexed; `page_size` is clamped server-side to avoid huge scans.
+#[derive(Deserialize)]
+struct ListParams {
+    #[serde(default)]
+    page: Option<i64>,
+    #[serde(default)]
+    page_size: Option<i64>,
+    #[serde(default)]
+    completed: Option<bool>,
+}
+
+// The response body for a paginated `GET /todos`, carrying the page of
+// items alongside the total row count so clients can compute page counts.
+#[derive(Serialize)]
+struct TodoList {
+    items: Vec<Todo>,
+    total: i64,
+}
+
+const MAX_PAGE_SIZE: i64 = 100;
+
+// Which SQL dialect we're talking to, detected from the `DATABASE_URL`
+// scheme. Needed only where the two backends genuinely diverge, such as
+// Postgres's `RETURNING` clause having no SQLite equivalent.
+#[derive(Clone, Copy, PartialEq)]
+enum Backend {
+    Postgres,
+    Sqlite,
+}
+
+impl Backend {
+    fn from_database_url(database_url: &str) -> Self {
+        if database_url.starts_with("sqlite:") {
+            Backend::Sqlite
+        } else {
+            Backend::Postgres
+        }
+    }
+}
+
 // The application state, which holds the database connection pool.
 // We use `Clone` so that the state can be shared across handlers.
+// The pool is an `AnyPool` so the same binary can run against Postgres in
+// production or an embedded SQLite file for local dev/CI.
 #[derive(Clone)]
 struct AppState {
-    pool: PgPool,
+    pool: AnyPool,
+    backend: Backend,
 }
 
 #[tokio::main]
@@ -42,23 +105,53 @@ async fn main() {
     // Render will use its own environment variable management.
     dotenv().ok();
 
+    // Initialize structured logging. Set `RUST_LOG` to control verbosity,
+    // e.g. `RUST_LOG=info` or `RUST_LOG=rust_crud_api=debug,tower_http=debug`.
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    // Install the drivers (Postgres, SQLite, ...) that `AnyPool` can pick between.
+    sqlx::any::install_default_drivers();
+
     // Get the database URL from the environment variables.
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let backend = Backend::from_database_url(&database_url);
 
-    // Create a PostgreSQL connection pool.
-    let pool = PgPoolOptions::new()
+    // Create a connection pool. `AnyPool` picks the concrete driver (Postgres
+    // or SQLite) from the `DATABASE_URL` scheme at connect time.
+    let pool = AnyPoolOptions::new()
         .max_connections(5)
         .connect(&database_url)
         .await
         .expect("Failed to create pool.");
 
+    // Run pending migrations so a fresh database gets the `todos` table
+    // before we start accepting requests. Postgres and SQLite need
+    // different DDL (e.g. `SERIAL` vs `INTEGER PRIMARY KEY AUTOINCREMENT`),
+    // so each backend gets its own migrations directory.
+    match backend {
+        Backend::Postgres => sqlx::migrate!("./migrations/postgres").run(&pool).await,
+        Backend::Sqlite => sqlx::migrate!("./migrations/sqlite").run(&pool).await,
+    }
+    .expect("Failed to run database migrations.");
+
     // Create the application state.
-    let app_state = AppState { pool };
+    let app_state = AppState { pool, backend };
 
     // Create the axum router.
     let app = Router::new()
+        .route("/health", get(health))
         .route("/todos", post(create_todo).get(get_todos))
-        .route("/todos/:id", get(get_todo).put(update_todo).delete(delete_todo))
+        .route(
+            "/todos/:id",
+            get(get_todo)
+                .put(update_todo)
+                .patch(patch_todo)
+                .delete(delete_todo),
+        )
+        .layer(cors_layer())
+        .layer(TraceLayer::new_for_http())
         .with_state(app_state);
 
     // Get the port from the environment or default to 3000.
@@ -79,17 +172,66 @@ async fn main() {
         .unwrap();
 }
 
+// Builds the CORS layer from `CORS_ALLOWED_ORIGINS` (a comma-separated list
+// of origins), defaulting to permissive (any origin) for local development.
+fn cors_layer() -> CorsLayer {
+    match env::var("CORS_ALLOWED_ORIGINS") {
+        Ok(origins) => {
+            let parsed = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|o| !o.is_empty())
+                .map(|o| o.parse().expect("invalid origin in CORS_ALLOWED_ORIGINS"))
+                .collect::<Vec<_>>();
+            CorsLayer::new().allow_origin(AllowOrigin::list(parsed))
+        }
+        Err(_) => CorsLayer::permissive(),
+    }
+}
+
+// Health/readiness check that pings the database. Load balancers and
+// orchestrators use this to tell "process is up" apart from "process can
+// actually serve requests".
+async fn health(State(state): State<AppState>) -> impl IntoResponse {
+    match sqlx::query("SELECT 1").execute(&state.pool).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "status": "ok", "db": "up" })),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "error", "db": "down" })),
+        )
+            .into_response(),
+    }
+}
+
 // API handler to create a new todo item.
 async fn create_todo(
     State(state): State<AppState>,
     Json(payload): Json<CreateTodo>,
 ) -> impl IntoResponse {
-    let result = sqlx::query_as::<_, Todo>(
-        "INSERT INTO todos (title) VALUES ($1) RETURNING id, title, completed",
-    )
-    .bind(payload.title)
-    .fetch_one(&state.pool)
-    .await;
+    if let Err(e) = payload.validate() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(e)).into_response();
+    }
+
+    let result = match state.backend {
+        Backend::Postgres => {
+            sqlx::query_as::<_, Todo>(
+                "INSERT INTO todos (title) VALUES ($1) RETURNING id, title, completed",
+            )
+            .bind(payload.title)
+            .fetch_one(&state.pool)
+            .await
+        }
+        // SQLite has no `RETURNING` on the versions bundled with sqlx's Any
+        // driver, so insert, then select the row back by its rowid.
+        Backend::Sqlite => {
+            insert_then_select(&state.pool, "INSERT INTO todos (title) VALUES ($1)", payload.title)
+                .await
+        }
+    };
 
     match result {
         Ok(todo) => (StatusCode::CREATED, Json(todo)).into_response(),
@@ -101,17 +243,81 @@ async fn create_todo(
     }
 }
 
-// API handler to get all todo items.
-async fn get_todos(State(state): State<AppState>) -> impl IntoResponse {
-    let result = sqlx::query_as::<_, Todo>("SELECT id, title, completed FROM todos")
-        .fetch_all(&state.pool)
-        .await;
+// Inserts a row and reads it back by `last_insert_rowid()`, used as the
+// SQLite fallback for backends without `RETURNING`.
+async fn insert_then_select(pool: &AnyPool, sql: &str, title: String) -> Result<Todo, sqlx::Error> {
+    // `last_insert_rowid()` is connection-scoped, so the insert and the
+    // lookup must run on the same connection rather than two pool checkouts.
+    let mut conn = pool.acquire().await?;
+    sqlx::query(sql).bind(title).execute(&mut *conn).await?;
+    sqlx::query_as::<_, Todo>(
+        "SELECT id, title, completed FROM todos WHERE id = (SELECT last_insert_rowid())",
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
 
-    match result {
-        Ok(todos) => (StatusCode::OK, Json(todos)).into_response(),
+// API handler to get a page of todo items, optionally filtered by completion status.
+async fn get_todos(
+    State(state): State<AppState>,
+    Query(params): Query<ListParams>,
+) -> impl IntoResponse {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(20).clamp(1, MAX_PAGE_SIZE);
+    let offset = (page - 1) * page_size;
+
+    let items_result = match params.completed {
+        Some(completed) => {
+            sqlx::query_as::<_, Todo>(
+                "SELECT id, title, completed FROM todos WHERE completed = $1 ORDER BY id LIMIT $2 OFFSET $3",
+            )
+            .bind(completed)
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&state.pool)
+            .await
+        }
+        None => {
+            sqlx::query_as::<_, Todo>(
+                "SELECT id, title, completed FROM todos ORDER BY id LIMIT $1 OFFSET $2",
+            )
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&state.pool)
+            .await
+        }
+    };
+
+    let items = match items_result {
+        Ok(items) => items,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch todos: {}", e),
+            )
+                .into_response()
+        }
+    };
+
+    let total_result = match params.completed {
+        Some(completed) => {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM todos WHERE completed = $1")
+                .bind(completed)
+                .fetch_one(&state.pool)
+                .await
+        }
+        None => {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM todos")
+                .fetch_one(&state.pool)
+                .await
+        }
+    };
+
+    match total_result {
+        Ok(total) => (StatusCode::OK, Json(TodoList { items, total })).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to fetch todos: {}", e),
+            format!("Failed to count todos: {}", e),
         )
             .into_response(),
     }
@@ -120,7 +326,7 @@ async fn get_todos(State(state): State<AppState>) -> impl IntoResponse {
 // API handler to get a single todo item by its ID.
 async fn get_todo(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
+    Path(id): Path<i64>,
 ) -> impl IntoResponse {
     let result = sqlx::query_as::<_, Todo>("SELECT id, title, completed FROM todos WHERE id = $1")
         .bind(id)
@@ -143,16 +349,108 @@ async fn get_todo(
 // API handler to update a todo item.
 async fn update_todo(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
+    Path(id): Path<i64>,
     Json(payload): Json<CreateTodo>,
 ) -> impl IntoResponse {
-    let result = sqlx::query_as::<_, Todo>(
-        "UPDATE todos SET title = $1, completed = false WHERE id = $2 RETURNING id, title, completed",
-    )
-    .bind(payload.title)
-    .bind(id)
-    .fetch_one(&state.pool)
-    .await;
+    if let Err(e) = payload.validate() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(e)).into_response();
+    }
+
+    let result = match state.backend {
+        Backend::Postgres => {
+            sqlx::query_as::<_, Todo>(
+                "UPDATE todos SET title = $1, completed = false WHERE id = $2 RETURNING id, title, completed",
+            )
+            .bind(payload.title)
+            .bind(id)
+            .fetch_one(&state.pool)
+            .await
+        }
+        Backend::Sqlite => {
+            update_then_select(
+                &state.pool,
+                "UPDATE todos SET title = $1, completed = false WHERE id = $2",
+                payload.title,
+                id,
+            )
+            .await
+        }
+    };
+
+    match result {
+        Ok(todo) => (StatusCode::OK, Json(todo)).into_response(),
+        Err(sqlx::Error::RowNotFound) => {
+            (StatusCode::NOT_FOUND, format!("Todo with id {} not found", id)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update todo: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+// Runs an `UPDATE` and reads the row back by id, used as the SQLite
+// fallback for backends without `RETURNING`.
+async fn update_then_select(
+    pool: &AnyPool,
+    sql: &str,
+    title: String,
+    id: i64,
+) -> Result<Todo, sqlx::Error> {
+    let res = sqlx::query(sql).bind(title).bind(id).execute(pool).await?;
+    if res.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    sqlx::query_as::<_, Todo>("SELECT id, title, completed FROM todos WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+// API handler to partially update a todo item, leaving omitted fields untouched.
+async fn patch_todo(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<UpdateTodo>,
+) -> impl IntoResponse {
+    if let Err(e) = payload.validate() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(e)).into_response();
+    }
+
+    let result = match state.backend {
+        Backend::Postgres => {
+            sqlx::query_as::<_, Todo>(
+                "UPDATE todos SET title = COALESCE($1, title), completed = COALESCE($2, completed) WHERE id = $3 RETURNING id, title, completed",
+            )
+            .bind(payload.title)
+            .bind(payload.completed)
+            .bind(id)
+            .fetch_one(&state.pool)
+            .await
+        }
+        Backend::Sqlite => {
+            let res = sqlx::query(
+                "UPDATE todos SET title = COALESCE($1, title), completed = COALESCE($2, completed) WHERE id = $3",
+            )
+            .bind(payload.title)
+            .bind(payload.completed)
+            .bind(id)
+            .execute(&state.pool)
+            .await;
+
+            match res {
+                Ok(res) if res.rows_affected() == 0 => Err(sqlx::Error::RowNotFound),
+                Ok(_) => {
+                    sqlx::query_as::<_, Todo>("SELECT id, title, completed FROM todos WHERE id = $1")
+                        .bind(id)
+                        .fetch_one(&state.pool)
+                        .await
+                }
+                Err(e) => Err(e),
+            }
+        }
+    };
 
     match result {
         Ok(todo) => (StatusCode::OK, Json(todo)).into_response(),
@@ -170,7 +468,7 @@ async fn update_todo(
 // API handler to delete a todo item.
 async fn delete_todo(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
+    Path(id): Path<i64>,
 ) -> impl IntoResponse {
     let result = sqlx::query("DELETE FROM todos WHERE id = $1")
         .bind(id)